@@ -14,7 +14,7 @@
 //! use sha2::{Sha256, Digest}; // can be any hasher(dyn Digest from `digest` crate)
 //!
 //! let src = b"hello vep!"; // <- 10 bytes
-//! let expanded = Vep(Sha256::new()).expand(src); // -> 10 * 32 bytes == `320 bytes`
+//! let expanded = Vep::new(Sha256::new()).expand(src); // -> 10 * 32 bytes == `320 bytes`
 //!
 //! assert_eq!(expanded.len(), Vep::<Sha256>::output_size_calc(src));
 //! ```
@@ -24,10 +24,21 @@
 //! # use vep::Vep;
 //! # use sha2::{Sha256, Digest};
 //! let src = b"hello vep!"; // <- 10 bytes
-//! let result = Vep(Sha256::new()).expand_and_then_reduce(src); // -> 320 bytes -> `32 bytes` (reduced)
+//! let result = Vep::new(Sha256::new()).expand_and_then_reduce(src); // -> 320 bytes -> `32 bytes` (reduced)
 //!
 //! assert_eq!(result.len(), Vep::<Sha256>::reduced_size_calc());
 //! ```
+//!
+//! ## XOF (arbitrary output length)
+//! ```rust
+//! # use vep::VepXof;
+//! use sha3::Shake128;
+//!
+//! let src = b"hello vep!"; // <- 10 bytes
+//! let expanded = VepXof::new(Shake128::default()).expand_to(src, 512); // -> exactly 512 bytes
+//!
+//! assert_eq!(expanded.len(), 512);
+//! ```
 
 #![deny(unsafe_code)]
 #![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
@@ -35,21 +46,46 @@
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
 
 #[cfg(feature = "std")]
 extern crate std;
 #[cfg(feature = "std")]
 use std::vec::Vec;
 
-use zeroize::Zeroize;
+use zeroize::{Zeroize, Zeroizing};
+
+use parts::XofReader;
 
 pub mod parts {
     pub use digest::generic_array::{ArrayLength, GenericArray};
-    pub use digest::Digest;
+    pub use digest::{Digest, ExtendableOutput, Update, XofReader};
     pub use typenum as BytesSize;
 }
 
+/// Errors produced by the allocation-avoiding `*_into` methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The output slice was smaller than the size computed by
+    /// `output_size_calc`/`reduced_size_calc`.
+    BufferTooSmall { needed: usize, got: usize },
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::BufferTooSmall { needed, got } => write!(
+                f,
+                "output buffer too small: needed {} bytes, got {}",
+                needed, got
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 pub trait Digester {
     type OutputSize: parts::ArrayLength<u8>;
     fn output_size() -> usize;
@@ -79,9 +115,62 @@ impl<D: parts::Digest> Digester for D {
     }
 }
 
-pub struct Vep<D: Digester>(pub D);
+/// Default work factor: each plaintext byte yields `byte + 1` inner-round
+/// iterations (`byte as u32 * 1 + 1`), one more than the byte value.
+const DEFAULT_COST: u32 = 1;
+
+pub struct Vep<D: Digester> {
+    pub digester: D,
+    key: Zeroizing<Vec<u8>>,
+    personalization: Zeroizing<Vec<u8>>,
+    cost: u32,
+}
 
 impl<D: Digester> Vep<D> {
+    pub fn new(digester: D) -> Self {
+        Self {
+            digester,
+            key: Zeroizing::new(Vec::new()),
+            personalization: Zeroizing::new(Vec::new()),
+            cost: DEFAULT_COST,
+        }
+    }
+    /// Build with an explicit work factor; see [`Vep::set_cost`].
+    pub fn with_cost(digester: D, cost: u32) -> Self {
+        Self::new(digester).set_cost(cost)
+    }
+    /// Mix a secret key (pepper) into every expansion round, so that the
+    /// same input expanded under different keys gives different output.
+    /// The key and the personalization string are fed to the digester back
+    /// to back with no separator or length prefix, so a key of `ab` with no
+    /// personalization gives the same output as a key of `a` with
+    /// personalization `b`.
+    pub fn with_key(mut self, key: impl AsRef<[u8]>) -> Self {
+        self.key = Zeroizing::new(key.as_ref().to_vec());
+        self
+    }
+    /// Mix a fixed context/domain string into every expansion round, so
+    /// outputs produced for one protocol can't be replayed into another.
+    pub fn with_personalization(mut self, context: impl AsRef<[u8]>) -> Self {
+        self.personalization = Zeroizing::new(context.as_ref().to_vec());
+        self
+    }
+    /// Set the work factor. Each plaintext byte, rather than deciding the
+    /// iteration count on its own (0-255 as dictated by the byte value),
+    /// now yields `byte as u32 * cost + cost` inner-round iterations: a
+    /// floor of `cost` even for a zero byte, and a ceiling tunable by the
+    /// caller to calibrate against a target running time, much like the
+    /// cost parameter of a PBKDF. `cost` is clamped to at least `1`, since
+    /// `0` would silently drop the floor guarantee above.
+    pub fn set_cost(mut self, cost: u32) -> Self {
+        self.cost = cost.max(1);
+        self
+    }
+    /// The configured work factor; see [`Vep::set_cost`].
+    #[inline]
+    pub fn cost(&self) -> u32 {
+        self.cost
+    }
     /// very cheap
     #[inline]
     pub fn output_size_calc(bytes: impl AsRef<[u8]>) -> usize {
@@ -94,36 +183,259 @@ impl<D: Digester> Vep<D> {
         D::output_size()
     }
     pub fn expand(mut self, bytes: impl AsRef<[u8]>) -> Vec<u8> {
-        let (last_salt, middle_output) = self.middle_process(bytes);
-        middle_output
+        let (mut last_salt, middle_output) = self.middle_process(bytes);
+        let out = middle_output
             .into_iter()
             .zip(last_salt.iter())
-            .flat_map(|(data, &salt)| {
-                self.0.update(data);
-                self.0.update(&[salt]);
-                self.0.finalize_reset()
-            })
-            .collect() // final output
+            .flat_map(|(data, &salt)| self.hash_round(data, salt))
+            .collect(); // final output
+        last_salt.zeroize();
+        out
+    }
+    /// Same as [`Vep::expand`], but writes into `out` instead of allocating,
+    /// returning the number of bytes written. `out` must be at least
+    /// [`Vep::output_size_calc`] bytes, or [`Error::BufferTooSmall`] is
+    /// returned. Useful on targets that want to avoid the `alloc`-backed
+    /// return value.
+    pub fn expand_into(mut self, bytes: impl AsRef<[u8]>, out: &mut [u8]) -> Result<usize, Error> {
+        let needed = Self::output_size_calc(bytes.as_ref());
+        if out.len() < needed {
+            return Err(Error::BufferTooSmall {
+                needed,
+                got: out.len(),
+            });
+        }
+        let (mut last_salt, middle_output) = self.middle_process(bytes);
+        let mut written = 0;
+        for (data, &salt) in middle_output.into_iter().zip(last_salt.iter()) {
+            let block = self.hash_round(data, salt);
+            out[written..written + block.len()].copy_from_slice(&block);
+            written += block.len();
+        }
+        last_salt.zeroize();
+        Ok(written)
     }
     pub fn expand_and_then_reduce(mut self, bytes: impl AsRef<[u8]>) -> Vec<u8> {
-        let (last_salt, middle_output) = self.middle_process(bytes);
-        middle_output
+        self.reduce_middle(bytes).to_vec()
+    }
+    /// Same as [`Vep::expand_and_then_reduce`], but writes into `out`
+    /// instead of allocating, returning the number of bytes written. `out`
+    /// must be at least [`Vep::reduced_size_calc`] bytes, or
+    /// [`Error::BufferTooSmall`] is returned.
+    pub fn expand_and_then_reduce_into(
+        mut self,
+        bytes: impl AsRef<[u8]>,
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        let needed = Self::reduced_size_calc();
+        if out.len() < needed {
+            return Err(Error::BufferTooSmall {
+                needed,
+                got: out.len(),
+            });
+        }
+        let reduced = self.reduce_middle(bytes);
+        out[..needed].copy_from_slice(&reduced);
+        Ok(needed)
+    }
+    /// Merkle-style reduction: combine the per-round blocks pairwise in a
+    /// balanced binary tree (hash adjacent pairs into a new level, repeat
+    /// until one block remains, carrying an unpaired trailing leaf up
+    /// unchanged) instead of the strictly sequential left-fold used by
+    /// [`Vep::expand_and_then_reduce`]. Under the `std` feature, independent
+    /// pairs within a level are hashed concurrently.
+    ///
+    /// This produces a different, position-sensitive digest from the
+    /// linear mode, so pick the one you mean to use deliberately rather
+    /// than treating the two as interchangeable. Each level of the tree
+    /// mixes in its own level index before combining pairs, so the tree
+    /// digest diverges from the linear fold's output at every leaf count,
+    /// including the 2- and 3-leaf levels produced by short inputs.
+    #[cfg(feature = "std")]
+    pub fn expand_and_then_reduce_tree(mut self, bytes: impl AsRef<[u8]>) -> Vec<u8>
+    where
+        D: Clone + Send,
+    {
+        let (mut last_salt, middle_output) = self.middle_process(bytes);
+        let mut level = middle_output
             .into_iter()
             .zip(last_salt.iter())
-            .map(|(data, &salt)| {
-                self.0.update(data);
-                self.0.update(&[salt]);
-                self.0.finalize_reset()
-            })
-            .collect::<Vec<parts::GenericArray<u8, D::OutputSize>>>()
+            .map(|(data, &salt)| self.hash_round(data, salt))
+            .collect::<Vec<parts::GenericArray<u8, D::OutputSize>>>();
+        last_salt.zeroize();
+
+        let mut level_index: u32 = 0;
+        while level.len() > 1 {
+            level = self.combine_level(level, level_index);
+            level_index += 1;
+        }
+        level.pop().unwrap().to_vec()
+    }
+    /// Same as the `std` implementation above, but without the `Send`
+    /// bound: `combine_level` reduces sequentially instead of spawning
+    /// threads, so a non-`Send` `Digester` (e.g. one wrapping `Rc`) works
+    /// too.
+    #[cfg(not(feature = "std"))]
+    pub fn expand_and_then_reduce_tree(mut self, bytes: impl AsRef<[u8]>) -> Vec<u8>
+    where
+        D: Clone,
+    {
+        let (mut last_salt, middle_output) = self.middle_process(bytes);
+        let mut level = middle_output
             .into_iter()
-            .reduce(|a, b| {
-                self.0.update(a);
-                self.0.update(b);
-                self.0.finalize_reset()
-            })
+            .zip(last_salt.iter())
+            .map(|(data, &salt)| self.hash_round(data, salt))
+            .collect::<Vec<parts::GenericArray<u8, D::OutputSize>>>();
+        last_salt.zeroize();
+
+        let mut level_index: u32 = 0;
+        while level.len() > 1 {
+            level = self.combine_level(level, level_index);
+            level_index += 1;
+        }
+        level.pop().unwrap().to_vec()
+    }
+    #[cfg(feature = "std")]
+    fn combine_level(
+        &mut self,
+        mut level: Vec<parts::GenericArray<u8, D::OutputSize>>,
+        level_index: u32,
+    ) -> Vec<parts::GenericArray<u8, D::OutputSize>>
+    where
+        D: Clone + Send,
+    {
+        let odd_leaf = if level.len() % 2 == 1 {
+            level.pop()
+        } else {
+            None
+        };
+        let mut next: Vec<parts::GenericArray<u8, D::OutputSize>> = std::thread::scope(|scope| {
+            level
+                .chunks(2)
+                .map(|pair| {
+                    let mut digester = self.digester.clone();
+                    let key = self.key.clone();
+                    let personalization = self.personalization.clone();
+                    let (mut a, mut b) = (pair[0].clone(), pair[1].clone());
+                    scope.spawn(move || {
+                        digester.update(key.as_slice());
+                        digester.update(personalization.as_slice());
+                        digester.update(level_index.to_le_bytes());
+                        digester.update(&a);
+                        digester.update(&b);
+                        let out = digester.finalize_reset();
+                        a.zeroize();
+                        b.zeroize();
+                        out
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().expect("tree reduction thread panicked"))
+                .collect()
+        });
+        // The clones consumed by the spawned threads are zeroized there;
+        // the originals left behind in `level` (the odd leaf already
+        // popped out above) still need wiping before they drop here.
+        for leaf in level.iter_mut() {
+            leaf.zeroize();
+        }
+        if let Some(leaf) = odd_leaf {
+            next.push(leaf);
+        }
+        next
+    }
+    #[cfg(not(feature = "std"))]
+    fn combine_level(
+        &mut self,
+        mut level: Vec<parts::GenericArray<u8, D::OutputSize>>,
+        level_index: u32,
+    ) -> Vec<parts::GenericArray<u8, D::OutputSize>>
+    where
+        D: Clone,
+    {
+        let odd_leaf = if level.len() % 2 == 1 {
+            level.pop()
+        } else {
+            None
+        };
+        let mut next = Vec::with_capacity(level.len() / 2 + 1);
+        let mut pairs = level.into_iter();
+        while let (Some(a), Some(b)) = (pairs.next(), pairs.next()) {
+            next.push(self.hash_pair_at_level(a, b, level_index));
+        }
+        if let Some(leaf) = odd_leaf {
+            next.push(leaf);
+        }
+        next
+    }
+    #[inline]
+    fn reduce_middle(
+        &mut self,
+        bytes: impl AsRef<[u8]>,
+    ) -> parts::GenericArray<u8, D::OutputSize> {
+        let (mut last_salt, middle_output) = self.middle_process(bytes);
+        let mut middle_output = middle_output
+            .into_iter()
+            .zip(last_salt.iter())
+            .map(|(data, &salt)| self.hash_round(data, salt))
+            .collect::<Vec<parts::GenericArray<u8, D::OutputSize>>>();
+        last_salt.zeroize();
+        middle_output
+            .drain(..)
+            .reduce(|a, b| self.hash_pair(a, b))
             .unwrap()
-            .to_vec()
+    }
+    #[inline]
+    fn hash_round(
+        &mut self,
+        mut data: parts::GenericArray<u8, D::OutputSize>,
+        salt: u8,
+    ) -> parts::GenericArray<u8, D::OutputSize> {
+        self.digester.update(self.key.as_slice());
+        self.digester.update(self.personalization.as_slice());
+        self.digester.update(&data);
+        self.digester.update([salt]);
+        let out = self.digester.finalize_reset();
+        data.zeroize();
+        out
+    }
+    #[inline]
+    fn hash_pair(
+        &mut self,
+        mut a: parts::GenericArray<u8, D::OutputSize>,
+        mut b: parts::GenericArray<u8, D::OutputSize>,
+    ) -> parts::GenericArray<u8, D::OutputSize> {
+        self.digester.update(self.key.as_slice());
+        self.digester.update(self.personalization.as_slice());
+        self.digester.update(&a);
+        self.digester.update(&b);
+        let out = self.digester.finalize_reset();
+        a.zeroize();
+        b.zeroize();
+        out
+    }
+    /// Like [`Vep::hash_pair`], but mixes in the tree level index so that
+    /// [`Vep::expand_and_then_reduce_tree`]'s pairwise combines never
+    /// collide with the plain left-fold used by [`Vep::reduce_middle`],
+    /// regardless of how many leaves a level has.
+    #[cfg(not(feature = "std"))]
+    #[inline]
+    fn hash_pair_at_level(
+        &mut self,
+        mut a: parts::GenericArray<u8, D::OutputSize>,
+        mut b: parts::GenericArray<u8, D::OutputSize>,
+        level: u32,
+    ) -> parts::GenericArray<u8, D::OutputSize> {
+        self.digester.update(self.key.as_slice());
+        self.digester.update(self.personalization.as_slice());
+        self.digester.update(level.to_le_bytes());
+        self.digester.update(&a);
+        self.digester.update(&b);
+        let out = self.digester.finalize_reset();
+        a.zeroize();
+        b.zeroize();
+        out
     }
     #[inline]
     fn middle_process(
@@ -148,20 +460,128 @@ impl<D: Digester> Vep<D> {
             salt = bytes[rev_i - i];
             let times = byte;
             buf.push(salt);
-            temp = self.0.digest(buf.as_slice());
-            for _ in 0..times {
-                temp = self.0.digest(temp.as_slice());
+            self.digester.update(self.key.as_slice());
+            self.digester.update(self.personalization.as_slice());
+            temp = self.digester.digest(buf.as_slice());
+            for _ in 0..(times as u32 * self.cost + self.cost) {
+                let next = self.digester.digest(temp.as_slice());
+                temp.zeroize();
+                temp = next;
             }
+            buf.zeroize();
             buf = temp.to_vec();
             last_salt.push(buf[0]);
             middle_output.push(temp);
         }
 
+        buf.zeroize();
         bytes.zeroize();
         (last_salt, middle_output)
     }
 }
 
+/// Expansion backend for [`VepXof`], built on an extendable-output function
+/// (e.g. SHAKE128/SHAKE256) instead of a fixed-size [`Digester`].
+pub trait Xofer {
+    fn update(&mut self, data: impl AsRef<[u8]>);
+    fn finalize_xof_reset_into(&mut self, out: &mut [u8]);
+}
+
+impl<X: parts::Update + parts::ExtendableOutput> Xofer for X {
+    #[inline]
+    fn update(&mut self, data: impl AsRef<[u8]>) {
+        parts::Update::update(self, data.as_ref());
+    }
+    #[inline]
+    fn finalize_xof_reset_into(&mut self, out: &mut [u8]) {
+        self.finalize_xof_reset().read(out);
+    }
+}
+
+/// how many bytes are squeezed per inner work-factor iteration
+const XOF_BLOCK_SIZE: usize = 32;
+
+/// Variable-length Expansion Pass, XOF flavor.
+///
+/// Unlike [`Vep`], which always emits `max(input_len, 2) * D::output_size()`
+/// bytes (see [`Vep::output_size_calc`]),
+/// `VepXof` is built on `digest::ExtendableOutput` (SHAKE128, SHAKE256, ...)
+/// so the caller chooses the exact output length via [`VepXof::expand_to`].
+pub struct VepXof<X: Xofer> {
+    pub xofer: X,
+    cost: u32,
+}
+
+impl<X: Xofer> VepXof<X> {
+    pub fn new(xofer: X) -> Self {
+        Self {
+            xofer,
+            cost: DEFAULT_COST,
+        }
+    }
+    /// Build with an explicit work factor; see [`VepXof::set_cost`].
+    pub fn with_cost(xofer: X, cost: u32) -> Self {
+        Self::new(xofer).set_cost(cost)
+    }
+    /// Set the work factor; see [`Vep::set_cost`] for the same floor
+    /// guarantee (`cost` is clamped to at least `1`) applied here to
+    /// [`VepXof::expand_to`]'s work-factor loop.
+    pub fn set_cost(mut self, cost: u32) -> Self {
+        self.cost = cost.max(1);
+        self
+    }
+    /// The configured work factor; see [`VepXof::set_cost`].
+    #[inline]
+    pub fn cost(&self) -> u32 {
+        self.cost
+    }
+    /// Expand `bytes` into exactly `out_len` bytes.
+    pub fn expand_to(mut self, bytes: impl AsRef<[u8]>, out_len: usize) -> Vec<u8> {
+        let mut bytes = match bytes.as_ref().len() {
+            // padding
+            0 => [0, 0].to_vec(),
+            1 => bytes.as_ref().to_vec().repeat(2),
+            _ => bytes.as_ref().to_vec(),
+        };
+        let bytes_len = bytes.len();
+        let rev_i = bytes_len - 1;
+        let mut salt;
+        let mut buf = Vec::from(bytes.as_slice());
+        let mut temp = [0u8; XOF_BLOCK_SIZE];
+
+        let per_round = out_len / bytes_len;
+        let remainder = out_len % bytes_len;
+        let mut out = Vec::with_capacity(out_len);
+
+        for (i, &byte) in bytes.iter().enumerate() {
+            salt = bytes[rev_i - i];
+            let times = byte;
+            buf.push(salt);
+
+            self.xofer.update(buf.as_slice());
+            self.xofer.finalize_xof_reset_into(&mut temp);
+            for _ in 0..(times as u32 * self.cost + self.cost) {
+                self.xofer.update(temp);
+                self.xofer.finalize_xof_reset_into(&mut temp);
+            }
+            buf.zeroize();
+            buf = temp.to_vec();
+
+            let this_round_len = per_round + if i < remainder { 1 } else { 0 };
+            let mut round_out = vec![0; this_round_len];
+            self.xofer.update(buf.as_slice());
+            self.xofer.update([salt]);
+            self.xofer.finalize_xof_reset_into(&mut round_out);
+            out.extend(round_out);
+        }
+
+        temp.zeroize();
+        buf.zeroize();
+        bytes.zeroize();
+        out
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,6 +593,8 @@ mod tests {
     use sha3::Sha3_512;
     // ---------------  sha2_384  ---------------
     use sha2::Sha384 as Sha2_384;
+    // ---------------  shake128  ---------------
+    use sha3::Shake128;
 
     fn filter_len(len: usize) -> usize {
         if len < 2 {
@@ -188,7 +610,7 @@ mod tests {
         let src_len = filter_len(src.len()); // 2 bytes
         eprintln!("\n'' = {} ({})\n", hex::encode(src), src_len);
 
-        let blake3_expanded = Vep(Hasher::new()).expand(src); // output = 32 bytes == 256 bits
+        let blake3_expanded = Vep::new(Hasher::new()).expand(src); // output = 32 bytes == 256 bits
         let b_len = blake3_expanded.len();
         assert_eq!(src_len * 32, b_len); // == 64 bytes == 512 bits
         assert_eq!(b_len, 64);
@@ -198,7 +620,7 @@ mod tests {
         let src_len = filter_len(src.len()); // 2 bytes
         eprintln!("\n'a' = {} ({})\n", hex::encode(src), src_len);
 
-        let sha2_expanded = Vep(Sha2_384::new()).expand(src); // output = 48 bytes == 384 bits
+        let sha2_expanded = Vep::new(Sha2_384::new()).expand(src); // output = 48 bytes == 384 bits
         let s2_len = sha2_expanded.len();
         assert_eq!(src_len * 48, s2_len); // == 96 bytes == 768 bits
         assert_eq!(s2_len, 96);
@@ -210,17 +632,17 @@ mod tests {
         let src_len = src.len(); // 12 bytes
         eprintln!("\n'hello world!' = {} ({})\n", hex::encode(src), src_len);
 
-        let blake3_expanded = Vep(Hasher::new()).expand(src); // output = 32 bytes == 256 bits
+        let blake3_expanded = Vep::new(Hasher::new()).expand(src); // output = 32 bytes == 256 bits
         let b_len = blake3_expanded.len();
         assert_eq!(src_len * 32, b_len); // == 384 bytes == 3072 bits
         assert_eq!(b_len, Vep::<Hasher>::output_size_calc(src));
 
-        let sha2_expanded = Vep(Sha2_384::new()).expand(src); // output = 48 bytes == 384 bits
+        let sha2_expanded = Vep::new(Sha2_384::new()).expand(src); // output = 48 bytes == 384 bits
         let s2_len = sha2_expanded.len();
         assert_eq!(src_len * 48, s2_len); // == 576 bytes == 4608 bits
         assert_eq!(s2_len, Vep::<Sha2_384>::output_size_calc(src));
 
-        let sha3_expanded = Vep(Sha3_512::new()).expand(src); // output = 64 bytes == 512 bits
+        let sha3_expanded = Vep::new(Sha3_512::new()).expand(src); // output = 64 bytes == 512 bits
         let s3_len = sha3_expanded.len();
         assert_eq!(src_len * 64, s3_len); // == 768 bytes == 6144 bits
         assert_eq!(s3_len, Vep::<Sha3_512>::output_size_calc(src));
@@ -247,17 +669,17 @@ mod tests {
         let src_len = src.len(); // 12 bytes
         eprintln!("\n'' = {} ({})\n", hex::encode(src), src_len);
 
-        let blake3_expanded = Vep(Hasher::new()).expand_and_then_reduce(src);
+        let blake3_expanded = Vep::new(Hasher::new()).expand_and_then_reduce(src);
         let b_len = blake3_expanded.len();
         assert_eq!(32, b_len); // 32 bytes == 256 bits
         assert_eq!(b_len, Vep::<Hasher>::reduced_size_calc());
 
-        let sha2_expanded = Vep(Sha2_384::new()).expand_and_then_reduce(src);
+        let sha2_expanded = Vep::new(Sha2_384::new()).expand_and_then_reduce(src);
         let s2_len = sha2_expanded.len();
         assert_eq!(48, s2_len); // 48 bytes == 384 bits
         assert_eq!(s2_len, Vep::<Sha2_384>::reduced_size_calc());
 
-        let sha3_expanded = Vep(Sha3_512::new()).expand_and_then_reduce(src);
+        let sha3_expanded = Vep::new(Sha3_512::new()).expand_and_then_reduce(src);
         let s3_len = sha3_expanded.len();
         assert_eq!(64, s3_len); // 64 bytes == 512 bits
         assert_eq!(s3_len, Vep::<Sha3_512>::reduced_size_calc());
@@ -266,13 +688,153 @@ mod tests {
         eprintln!("vep(blake3_256) = {} ({})\n", hex, b_len);
         assert_eq!(
             hex,
-            "78e74c2be51e45d39331b3b25359b1122f3a0f1e042379aafa85ca2651352438"
+            "aa6205e7f9ffa3ee943d3e684ddbfa65375f5786df465212a05f8df4b1c0b1ce"
         );
         let hex = hex::encode(sha2_expanded);
         eprintln!("vep(sha2_384) = {} ({})\n", hex, s2_len);
-        assert_eq!(hex, "21e977feb8e749c591c10adc3fe718302680f0b80750aed635de4c9a1d3529362092aed43529cc4fecca1baf119e00c1");
+        assert_eq!(hex, "969d9fca6e4ae9736bb9e2aa96d528a971254ada0b8fba6f35182fe944265941fe56ba3c0c0cc72b27469586d47ac6ce");
         let hex = hex::encode(sha3_expanded);
         eprintln!("vep(sha3_512) = {} ({})\n", hex, s3_len);
-        assert_eq!(hex, "760974c924b7ca24b447a53e2bd82fc3112ab2334cf8e2a3ebe22fff073aee4d795ea0e5d5ce82facb1b228fc531c92bb71c4f6feebea1099863b564c89e8310");
+        assert_eq!(hex, "9fda886c3f53dabec70626aeebb2aad02d0c1affff1aeb165c7c1d2325c71ccac906007137b69ad700e4eb04e75f0a3a98b20bae324bbbe700a0b3ec3bb897b1");
+    }
+    #[test]
+    fn expand_and_then_reduce_tree_diverges_from_linear() {
+        // Short inputs (0-3 bytes) pad down to 2- or 3-leaf reduction
+        // levels, which is exactly where the tree mode used to collapse
+        // into the same left-fold as the linear mode.
+        for src in [&b""[..], &b"a"[..], &b"ab"[..], &b"abc"[..], &b"hello world!"[..]] {
+            let linear = Vep::new(Sha2_384::new()).expand_and_then_reduce(src);
+            let tree = Vep::new(Sha2_384::new()).expand_and_then_reduce_tree(src);
+            eprintln!(
+                "'{}': linear = {}, tree = {}\n",
+                String::from_utf8_lossy(src),
+                hex::encode(&linear),
+                hex::encode(&tree)
+            );
+            assert_eq!(linear.len(), tree.len());
+            assert_ne!(linear, tree);
+        }
+    }
+    #[test]
+    fn set_cost_floors_at_one() {
+        let src = b"hello world!";
+
+        assert_eq!(Vep::new(Sha2_384::new()).set_cost(0).cost(), 1);
+        assert_eq!(Vep::new(Sha2_384::new()).cost(), 1);
+        assert_eq!(Vep::<Sha2_384>::with_cost(Sha2_384::new(), 0).cost(), 1);
+
+        // A zero-cost request is clamped to the same floor as an explicit
+        // 1, so both must expand to the same output...
+        let zero_cost = Vep::new(Sha2_384::new()).set_cost(0).expand(src);
+        let floor_cost = Vep::new(Sha2_384::new()).set_cost(1).expand(src);
+        assert_eq!(zero_cost, floor_cost);
+
+        // ...while a higher cost changes the output.
+        let higher_cost = Vep::new(Sha2_384::new()).set_cost(2).expand(src);
+        assert_ne!(floor_cost, higher_cost);
+    }
+    #[test]
+    fn xof_expand_to_exact_len() {
+        for (src, out_len) in [
+            (&b""[..], 0),
+            (&b""[..], 1),
+            (&b"a"[..], 1),
+            (&b"hello world!"[..], 7), // not a multiple of src_len
+            (&b"hello world!"[..], 100),
+        ] {
+            let out = VepXof::new(Shake128::default()).expand_to(src, out_len);
+            assert_eq!(out.len(), out_len);
+        }
+    }
+    #[test]
+    fn xof_set_cost_floors_at_one() {
+        let src = b"hello world!";
+
+        assert_eq!(VepXof::new(Shake128::default()).set_cost(0).cost(), 1);
+        assert_eq!(VepXof::new(Shake128::default()).cost(), 1);
+        assert_eq!(
+            VepXof::with_cost(Shake128::default(), 0).cost(),
+            1
+        );
+
+        let zero_cost = VepXof::new(Shake128::default())
+            .set_cost(0)
+            .expand_to(src, 64);
+        let floor_cost = VepXof::new(Shake128::default())
+            .set_cost(1)
+            .expand_to(src, 64);
+        assert_eq!(zero_cost, floor_cost);
+
+        let higher_cost = VepXof::new(Shake128::default())
+            .set_cost(2)
+            .expand_to(src, 64);
+        assert_ne!(floor_cost, higher_cost);
+    }
+    #[test]
+    fn keyed_and_personalized_outputs_differ_from_unkeyed() {
+        let src = b"hello world!";
+
+        let plain = Vep::new(Sha2_384::new()).expand(src);
+        let keyed = Vep::new(Sha2_384::new()).with_key(b"pepper").expand(src);
+        let personalized = Vep::new(Sha2_384::new())
+            .with_personalization(b"my-protocol-v1")
+            .expand(src);
+        let both = Vep::new(Sha2_384::new())
+            .with_key(b"pepper")
+            .with_personalization(b"my-protocol-v1")
+            .expand(src);
+
+        assert_ne!(plain, keyed);
+        assert_ne!(plain, personalized);
+        assert_ne!(plain, both);
+        assert_ne!(keyed, personalized);
+        assert_ne!(keyed, both);
+        assert_ne!(personalized, both);
+    }
+    #[test]
+    fn expand_into_matches_expand_and_rejects_small_buffer() {
+        let src = b"hello world!";
+
+        let allocated = Vep::new(Sha2_384::new()).expand(src);
+        let mut buf = vec![0u8; allocated.len()];
+        let written = Vep::new(Sha2_384::new()).expand_into(src, &mut buf).unwrap();
+        assert_eq!(written, allocated.len());
+        assert_eq!(buf, allocated);
+
+        let mut too_small = vec![0u8; allocated.len() - 1];
+        let err = Vep::new(Sha2_384::new())
+            .expand_into(src, &mut too_small)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            Error::BufferTooSmall {
+                needed: allocated.len(),
+                got: allocated.len() - 1,
+            }
+        );
+    }
+    #[test]
+    fn expand_and_then_reduce_into_matches_expand_and_then_reduce_and_rejects_small_buffer() {
+        let src = b"hello world!";
+
+        let allocated = Vep::new(Sha2_384::new()).expand_and_then_reduce(src);
+        let mut buf = vec![0u8; allocated.len()];
+        let written = Vep::new(Sha2_384::new())
+            .expand_and_then_reduce_into(src, &mut buf)
+            .unwrap();
+        assert_eq!(written, allocated.len());
+        assert_eq!(buf, allocated);
+
+        let mut too_small = vec![0u8; allocated.len() - 1];
+        let err = Vep::new(Sha2_384::new())
+            .expand_and_then_reduce_into(src, &mut too_small)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            Error::BufferTooSmall {
+                needed: allocated.len(),
+                got: allocated.len() - 1,
+            }
+        );
     }
 }